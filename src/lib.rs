@@ -1,5 +1,7 @@
 use std::ascii::AsciiExt;
 use std::char;
+use std::fmt;
+use std::str::FromStr;
 
 /// Provides a fast method for testing character membership of a purely ASCII set.
 ///
@@ -66,6 +68,62 @@ impl AsciiSet {
         self.insert_byte(c as u8);
     }
 
+    /// Tests whether this set contains `c`, ignoring ASCII case.
+    ///
+    /// # Examples
+    /// ```
+    /// use ascii_set::AsciiSet;
+    /// let a = AsciiSet::from_ranges(vec![('a', 'a')]);
+    /// assert!(a.contains_char_ignore_case('A'));
+    /// ```
+    #[inline]
+    pub fn contains_char_ignore_case(&self, c: char) -> bool {
+        if !c.is_ascii() {
+            return false;
+        }
+        let b = c as u8;
+        self.contains_byte(b) || self.contains_byte(swap_ascii_case(b))
+    }
+
+    /// Adds both ASCII cases of `c` to this set.
+    ///
+    /// If `c` isn't an ASCII letter, this is the same as `insert_char`.
+    ///
+    /// # Panics
+    ///  - if `c` falls outside the ASCII range.
+    pub fn insert_char_ignore_case(&mut self, c: char) {
+        if !c.is_ascii() {
+            panic!("only ASCII chars allowed");
+        }
+        let b = c as u8;
+        self.insert_byte(b);
+        self.insert_byte(swap_ascii_case(b));
+    }
+
+    /// Returns the set of all ASCII cases of the characters in `self`.
+    ///
+    /// The result is closed under ASCII case: whenever it contains a letter, it contains that
+    /// letter's other case too.
+    ///
+    /// # Examples
+    /// ```
+    /// use ascii_set::AsciiSet;
+    /// let a = AsciiSet::from_ranges(vec![('a', 'e')]);
+    /// assert_eq!(a.to_case_insensitive(), AsciiSet::from_ranges(vec![('a', 'e'), ('A', 'E')]));
+    /// ```
+    pub fn to_case_insensitive(&self) -> AsciiSet {
+        let upper_mask = AsciiSet::upper_case_letters().hi_mask;
+        let lower_mask = AsciiSet::lower_case_letters().hi_mask;
+        let upper_bits = self.hi_mask & upper_mask;
+        let lower_bits = self.hi_mask & lower_mask;
+        // Upper- and lower-case letters sit exactly 32 bit positions apart within `hi_mask`
+        // (since their codepoints are 32 apart), so folding is just a pair of shifts.
+        AsciiSet {
+            lo_mask: self.lo_mask,
+            hi_mask: self.hi_mask | (upper_bits << 32) | (lower_bits >> 32),
+        }
+    }
+
     /// Creates a new, empty, `AsciiSet`.
     pub fn new() -> AsciiSet {
         AsciiSet {
@@ -222,6 +280,590 @@ impl AsciiSet {
             hi_mask: 0,
         }
     }
+
+    /// Returns the set of all hexadecimal digits (`0-9`, `a-f`, `A-F`).
+    pub fn hex_digits() -> AsciiSet {
+        AsciiSet {
+            lo_mask: 0b0000001111111111000000000000000000000000000000000000000000000000,
+            hi_mask: 0b0000000000000000000000000111111000000000000000000000000001111110,
+        }
+    }
+
+    /// Returns the set of all letters and digits.
+    pub fn alphanumeric() -> AsciiSet {
+        AsciiSet {
+            lo_mask: 0b0000001111111111000000000000000000000000000000000000000000000000,
+            hi_mask: 0b0000011111111111111111111111111000000111111111111111111111111110,
+        }
+    }
+
+    /// Returns the set of ASCII whitespace characters: space, tab, LF, VT, FF, and CR.
+    pub fn whitespace() -> AsciiSet {
+        AsciiSet {
+            lo_mask: 0b0000000000000000000000000000000100000000000000000011111000000000,
+            hi_mask: 0,
+        }
+    }
+
+    /// Returns the set consisting of just space and tab.
+    pub fn blank() -> AsciiSet {
+        AsciiSet {
+            lo_mask: 0b0000000000000000000000000000000100000000000000000000001000000000,
+            hi_mask: 0,
+        }
+    }
+
+    /// Returns the set of ASCII control characters: `0x00` through `0x1F`, plus DEL (`0x7F`).
+    pub fn control() -> AsciiSet {
+        AsciiSet {
+            lo_mask: 0b0000000000000000000000000000000011111111111111111111111111111111,
+            hi_mask: 0b1000000000000000000000000000000000000000000000000000000000000000,
+        }
+    }
+
+    /// Returns the set of ASCII punctuation characters.
+    pub fn punctuation() -> AsciiSet {
+        AsciiSet {
+            lo_mask: 0b1111110000000000111111111111111000000000000000000000000000000000,
+            hi_mask: 0b0111100000000000000000000000000111111000000000000000000000000001,
+        }
+    }
+
+    /// Returns the set of ASCII graphic (visible, non-space) characters.
+    pub fn graphic() -> AsciiSet {
+        AsciiSet {
+            lo_mask: 0b1111111111111111111111111111111000000000000000000000000000000000,
+            hi_mask: 0b0111111111111111111111111111111111111111111111111111111111111111,
+        }
+    }
+
+    /// Returns the set of ASCII printable characters: the graphic characters, plus space.
+    pub fn printable() -> AsciiSet {
+        AsciiSet {
+            lo_mask: 0b1111111111111111111111111111111100000000000000000000000000000000,
+            hi_mask: 0b0111111111111111111111111111111111111111111111111111111111111111,
+        }
+    }
+
+    /// Builds the pair of 16-byte nibble lookup tables used by the batch scanning methods
+    /// (`count_in`, `find_first`, `retain_matching`).
+    ///
+    /// A byte `b` is a member of this set iff `lo_table[b & 0x0F] & hi_table[b >> 4] != 0`. Since
+    /// every ASCII codepoint has a high nibble `h = b >> 4` in `0..8`, `hi_table[h] = 1 << h` for
+    /// `h < 8` and `0` for `h >= 8` (which makes every byte `>= 128` fail to match, as required).
+    /// `lo_table[l]` then ORs together `1 << h` for each `h` such that `(h << 4) | l` belongs to
+    /// the set. This is the classic nibble-lookup trick behind vectorized character classification
+    /// (two gathers, an AND, and a compare-to-zero), and it also gives a fast branchless scalar
+    /// fallback.
+    fn nibble_tables(&self) -> ([u8; 16], [u8; 16]) {
+        let mut hi_table = [0u8; 16];
+        for (h, entry) in hi_table.iter_mut().enumerate().take(8) {
+            *entry = 1 << h;
+        }
+        let mut lo_table = [0u8; 16];
+        for (l, entry) in lo_table.iter_mut().enumerate() {
+            let mut bits = 0u8;
+            for h in 0..8 {
+                if self.contains(((h << 4) | l) as u32) {
+                    bits |= 1 << h;
+                }
+            }
+            *entry = bits;
+        }
+        (lo_table, hi_table)
+    }
+
+    /// Counts how many bytes of `bytes` belong to this set.
+    ///
+    /// This scans 16 bytes at a time using the nibble lookup tables (via SSSE3 `pshufb` when
+    /// available), which is substantially faster than calling `contains_byte` in a loop.
+    ///
+    /// # Examples
+    /// ```
+    /// use ascii_set::AsciiSet;
+    /// let digits = AsciiSet::digits();
+    /// assert_eq!(digits.count_in(b"abc123xyz456"), 6);
+    /// ```
+    pub fn count_in(&self, bytes: &[u8]) -> usize {
+        let (lo_table, hi_table) = self.nibble_tables();
+        #[cfg(target_arch = "x86_64")]
+        {
+            if is_x86_feature_detected!("ssse3") {
+                return unsafe { simd::count_in(&lo_table, &hi_table, bytes) };
+            }
+        }
+        count_in_scalar(&lo_table, &hi_table, bytes)
+    }
+
+    /// Finds the index of the first byte of `bytes` that belongs to this set.
+    ///
+    /// # Examples
+    /// ```
+    /// use ascii_set::AsciiSet;
+    /// let digits = AsciiSet::digits();
+    /// assert_eq!(digits.find_first(b"abc123"), Some(3));
+    /// assert_eq!(digits.find_first(b"abcxyz"), None);
+    /// ```
+    pub fn find_first(&self, bytes: &[u8]) -> Option<usize> {
+        let (lo_table, hi_table) = self.nibble_tables();
+        #[cfg(target_arch = "x86_64")]
+        {
+            if is_x86_feature_detected!("ssse3") {
+                return unsafe { simd::find_first(&lo_table, &hi_table, bytes) };
+            }
+        }
+        find_first_scalar(&lo_table, &hi_table, bytes)
+    }
+
+    /// Returns the subset of `bytes` that belongs to this set, preserving order.
+    ///
+    /// # Examples
+    /// ```
+    /// use ascii_set::AsciiSet;
+    /// let digits = AsciiSet::digits();
+    /// assert_eq!(digits.retain_matching(b"abc123xyz456"), b"123456");
+    /// ```
+    pub fn retain_matching(&self, bytes: &[u8]) -> Vec<u8> {
+        let (lo_table, hi_table) = self.nibble_tables();
+        #[cfg(target_arch = "x86_64")]
+        {
+            if is_x86_feature_detected!("ssse3") {
+                return unsafe { simd::retain_matching(&lo_table, &hi_table, bytes) };
+            }
+        }
+        retain_matching_scalar(&lo_table, &hi_table, bytes)
+    }
+
+    /// Returns the number of characters in this set.
+    ///
+    /// # Examples
+    /// ```
+    /// use ascii_set::AsciiSet;
+    /// assert_eq!(AsciiSet::digits().len(), 10);
+    /// assert_eq!(AsciiSet::new().len(), 0);
+    /// ```
+    pub fn len(&self) -> usize {
+        (self.lo_mask.count_ones() + self.hi_mask.count_ones()) as usize
+    }
+
+    /// Returns `true` if this set contains no characters.
+    pub fn is_empty(&self) -> bool {
+        self.lo_mask == 0 && self.hi_mask == 0
+    }
+
+    /// Returns an iterator over the bytes of this set, in increasing order.
+    ///
+    /// # Examples
+    /// ```
+    /// use ascii_set::AsciiSet;
+    /// let a = AsciiSet::from_ranges(vec![('a', 'c')]);
+    /// assert_eq!(a.iter_bytes().collect::<Vec<_>>(), vec![b'a', b'b', b'c']);
+    /// ```
+    pub fn iter_bytes(&self) -> IterBytes {
+        IterBytes {
+            lo_mask: self.lo_mask,
+            hi_mask: self.hi_mask,
+        }
+    }
+
+    /// Returns an iterator over the characters of this set, in increasing order.
+    ///
+    /// # Examples
+    /// ```
+    /// use ascii_set::AsciiSet;
+    /// let a = AsciiSet::from_ranges(vec![('a', 'c')]);
+    /// assert_eq!(a.iter().collect::<Vec<_>>(), vec!['a', 'b', 'c']);
+    /// ```
+    pub fn iter(&self) -> Iter {
+        Iter { bytes: self.iter_bytes() }
+    }
+}
+
+/// An iterator over the bytes of an `AsciiSet`, in increasing order.
+///
+/// Created by `AsciiSet::iter_bytes`.
+pub struct IterBytes {
+    lo_mask: u64,
+    hi_mask: u64,
+}
+
+impl Iterator for IterBytes {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        if self.lo_mask != 0 {
+            let bit = self.lo_mask.trailing_zeros();
+            self.lo_mask &= self.lo_mask - 1;
+            Some(bit as u8)
+        } else if self.hi_mask != 0 {
+            let bit = self.hi_mask.trailing_zeros();
+            self.hi_mask &= self.hi_mask - 1;
+            Some((bit + 64) as u8)
+        } else {
+            None
+        }
+    }
+}
+
+/// An iterator over the characters of an `AsciiSet`, in increasing order.
+///
+/// Created by `AsciiSet::iter`.
+pub struct Iter {
+    bytes: IterBytes,
+}
+
+impl Iterator for Iter {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        self.bytes.next().map(|b| b as char)
+    }
+}
+
+/// An error returned by `AsciiSet::from_str` when its argument isn't a valid set representation.
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct ParseAsciiSetError {
+    message: String,
+}
+
+impl fmt::Display for ParseAsciiSetError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid AsciiSet syntax: {}", self.message)
+    }
+}
+
+impl std::error::Error for ParseAsciiSetError {
+    fn description(&self) -> &str {
+        &self.message
+    }
+}
+
+/// Prints the set as a sequence of collapsed inclusive ranges, e.g. `"0-9A-Za-z"`. Members that
+/// aren't ASCII graphic characters (including `-` and `\`, which would otherwise be ambiguous)
+/// are printed as `\xNN` escapes. The result can be parsed back with `FromStr`.
+impl fmt::Display for AsciiSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut bytes = self.iter_bytes().peekable();
+        while let Some(start) = bytes.next() {
+            let mut end = start;
+            while bytes.peek() == Some(&(end + 1)) {
+                end = bytes.next().unwrap();
+            }
+            write_escaped_byte(f, start)?;
+            if end > start {
+                f.write_str("-")?;
+                write_escaped_byte(f, end)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn write_escaped_byte(f: &mut fmt::Formatter, b: u8) -> fmt::Result {
+    let c = b as char;
+    if c.is_ascii_graphic() && c != '-' && c != '\\' {
+        write!(f, "{}", c)
+    } else {
+        write!(f, "\\x{:02X}", b)
+    }
+}
+
+/// Parses the range syntax produced by `Display`: a sequence of either single characters or
+/// `char-char` ranges, where a character is either a `\xNN` escape or any other literal ASCII
+/// character except `\`. A `-` that follows the first character of an item makes that item a
+/// range; a `-` anywhere else is read as a literal member (`Display` itself always escapes it).
+impl FromStr for AsciiSet {
+    type Err = ParseAsciiSetError;
+
+    fn from_str(s: &str) -> Result<AsciiSet, ParseAsciiSetError> {
+        let mut bytes = s.bytes().peekable();
+        let mut ranges = Vec::new();
+        while bytes.peek().is_some() {
+            let start = parse_set_byte(&mut bytes)?;
+            let end = if bytes.peek() == Some(&b'-') {
+                bytes.next();
+                parse_set_byte(&mut bytes)?
+            } else {
+                start
+            };
+            if end < start {
+                return Err(ParseAsciiSetError {
+                    message: "range start must not be greater than its end".to_string(),
+                });
+            }
+            ranges.push((start as char, end as char));
+        }
+        Ok(AsciiSet::from_ranges(ranges))
+    }
+}
+
+fn parse_set_byte<I>(bytes: &mut ::std::iter::Peekable<I>) -> Result<u8, ParseAsciiSetError>
+        where I: Iterator<Item=u8> {
+    match bytes.next() {
+        Some(b'\\') => {
+            if bytes.next() != Some(b'x') {
+                return Err(ParseAsciiSetError { message: "expected \\x escape".to_string() });
+            }
+            let hi = parse_hex_digit(bytes)?;
+            let lo = parse_hex_digit(bytes)?;
+            let b = hi * 16 + lo;
+            if b < 128 {
+                Ok(b)
+            } else {
+                Err(ParseAsciiSetError { message: "only ASCII chars allowed".to_string() })
+            }
+        }
+        Some(b) if b < 128 => Ok(b),
+        Some(_) => Err(ParseAsciiSetError { message: "only ASCII chars allowed".to_string() }),
+        None => Err(ParseAsciiSetError { message: "unexpected end of input".to_string() }),
+    }
+}
+
+fn parse_hex_digit<I>(bytes: &mut ::std::iter::Peekable<I>) -> Result<u8, ParseAsciiSetError>
+        where I: Iterator<Item=u8> {
+    match bytes.next() {
+        Some(b) => (b as char).to_digit(16)
+            .map(|d| d as u8)
+            .ok_or_else(|| ParseAsciiSetError { message: "invalid hex digit".to_string() }),
+        None => Err(ParseAsciiSetError { message: "unexpected end of input".to_string() }),
+    }
+}
+
+/// Pattern-style operations for using an `AsciiSet` as a character class against `&str`, similar
+/// to how the standard string methods accept a set of chars. These all work on raw bytes (via
+/// `contains_byte`/`count_in`/`find_first`), so they stay ASCII-safe: a non-ASCII UTF-8 byte is
+/// never a member, and an ASCII byte is always its own char, so byte indices line up with char
+/// boundaries.
+impl AsciiSet {
+    /// Returns the byte index of the first character of `s` that belongs to this set.
+    ///
+    /// # Examples
+    /// ```
+    /// use ascii_set::AsciiSet;
+    /// assert_eq!(AsciiSet::digits().find("abc123"), Some(3));
+    /// ```
+    pub fn find(&self, s: &str) -> Option<usize> {
+        self.find_first(s.as_bytes())
+    }
+
+    /// Returns the byte index of the last character of `s` that belongs to this set.
+    ///
+    /// # Examples
+    /// ```
+    /// use ascii_set::AsciiSet;
+    /// assert_eq!(AsciiSet::digits().rfind("a1b2c3"), Some(5));
+    /// ```
+    pub fn rfind(&self, s: &str) -> Option<usize> {
+        s.as_bytes().iter().rposition(|&b| self.contains_byte(b))
+    }
+
+    /// Counts the characters of `s` that belong to this set.
+    ///
+    /// # Examples
+    /// ```
+    /// use ascii_set::AsciiSet;
+    /// assert_eq!(AsciiSet::digits().matches_count("a1b22c"), 3);
+    /// ```
+    pub fn matches_count(&self, s: &str) -> usize {
+        self.count_in(s.as_bytes())
+    }
+
+    /// Splits `s` on every character that belongs to this set, similar to `str::split`.
+    ///
+    /// # Examples
+    /// ```
+    /// use ascii_set::AsciiSet;
+    /// let commas = AsciiSet::from_chars(",".chars());
+    /// assert_eq!(commas.split("a,bc,,d").collect::<Vec<_>>(), vec!["a", "bc", "", "d"]);
+    /// ```
+    pub fn split<'a>(&self, s: &'a str) -> Split<'a> {
+        Split {
+            lo_mask: self.lo_mask,
+            hi_mask: self.hi_mask,
+            rest: Some(s),
+        }
+    }
+
+    /// Strips leading characters of `s` that belong to this set.
+    pub fn trim_start<'a>(&self, s: &'a str) -> &'a str {
+        let idx = s.as_bytes().iter().position(|&b| !self.contains_byte(b)).unwrap_or(s.len());
+        &s[idx..]
+    }
+
+    /// Strips trailing characters of `s` that belong to this set.
+    pub fn trim_end<'a>(&self, s: &'a str) -> &'a str {
+        let idx = s.as_bytes().iter().rposition(|&b| !self.contains_byte(b))
+            .map(|i| i + 1)
+            .unwrap_or(0);
+        &s[..idx]
+    }
+
+    /// Strips leading and trailing characters of `s` that belong to this set.
+    ///
+    /// # Examples
+    /// ```
+    /// use ascii_set::AsciiSet;
+    /// assert_eq!(AsciiSet::whitespace().trim("  hi  "), "hi");
+    /// ```
+    pub fn trim<'a>(&self, s: &'a str) -> &'a str {
+        self.trim_end(self.trim_start(s))
+    }
+}
+
+/// An iterator over substrings of a `&str`, split on characters belonging to an `AsciiSet`.
+///
+/// Created by `AsciiSet::split`.
+pub struct Split<'a> {
+    lo_mask: u64,
+    hi_mask: u64,
+    rest: Option<&'a str>,
+}
+
+impl<'a> Split<'a> {
+    #[inline]
+    fn contains(&self, b: u8) -> bool {
+        AsciiSet { lo_mask: self.lo_mask, hi_mask: self.hi_mask }.contains_byte(b)
+    }
+}
+
+impl<'a> Iterator for Split<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        let s = self.rest?;
+        match s.as_bytes().iter().position(|&b| self.contains(b)) {
+            Some(idx) => {
+                self.rest = Some(&s[idx + 1..]);
+                Some(&s[..idx])
+            }
+            None => {
+                self.rest = None;
+                Some(s)
+            }
+        }
+    }
+}
+
+/// Branchless check for `b'a'..=b'z'`, equivalent to `byte.is_ascii_lowercase()` but kept in the
+/// same performance class as `contains_byte`.
+#[inline]
+fn is_ascii_lowercase_fast(byte: u8) -> bool {
+    (byte.wrapping_add(0x1f) & !byte.wrapping_add(0x05) & 0x80) != 0
+}
+
+/// Branchless check for `b'A'..=b'Z'`.
+#[inline]
+fn is_ascii_uppercase_fast(byte: u8) -> bool {
+    is_ascii_lowercase_fast(byte ^ 0x20)
+}
+
+/// Toggles the case of an ASCII letter; leaves any other byte unchanged.
+#[inline]
+fn swap_ascii_case(byte: u8) -> u8 {
+    if is_ascii_lowercase_fast(byte) || is_ascii_uppercase_fast(byte) {
+        byte ^ 0x20
+    } else {
+        byte
+    }
+}
+
+#[inline]
+fn table_lookup(lo_table: &[u8; 16], hi_table: &[u8; 16], b: u8) -> bool {
+    let lo = (b & 0x0F) as usize;
+    let hi = (b >> 4) as usize;
+    (lo_table[lo] & hi_table[hi]) != 0
+}
+
+fn count_in_scalar(lo_table: &[u8; 16], hi_table: &[u8; 16], bytes: &[u8]) -> usize {
+    bytes.iter().filter(|&&b| table_lookup(lo_table, hi_table, b)).count()
+}
+
+fn find_first_scalar(lo_table: &[u8; 16], hi_table: &[u8; 16], bytes: &[u8]) -> Option<usize> {
+    bytes.iter().position(|&b| table_lookup(lo_table, hi_table, b))
+}
+
+fn retain_matching_scalar(lo_table: &[u8; 16], hi_table: &[u8; 16], bytes: &[u8]) -> Vec<u8> {
+    bytes.iter().cloned().filter(|&b| table_lookup(lo_table, hi_table, b)).collect()
+}
+
+/// SSSE3-accelerated scanning, using `pshufb` to do the two nibble-table gathers 16 bytes at a
+/// time. Falls back to the scalar table lookup for any bytes left over at the end.
+#[cfg(target_arch = "x86_64")]
+mod simd {
+    use std::arch::x86_64::*;
+
+    /// # Safety
+    /// The caller must ensure the `ssse3` target feature is available.
+    #[target_feature(enable = "ssse3")]
+    pub unsafe fn count_in(lo_table: &[u8; 16], hi_table: &[u8; 16], bytes: &[u8]) -> usize {
+        let lo_vec = _mm_loadu_si128(lo_table.as_ptr() as *const __m128i);
+        let hi_vec = _mm_loadu_si128(hi_table.as_ptr() as *const __m128i);
+        let mut total = 0usize;
+        let mut chunks = bytes.chunks_exact(16);
+        for chunk in &mut chunks {
+            let member_mask = classify16(lo_vec, hi_vec, chunk);
+            total += member_mask.count_ones() as usize;
+        }
+        total + super::count_in_scalar(lo_table, hi_table, chunks.remainder())
+    }
+
+    /// # Safety
+    /// The caller must ensure the `ssse3` target feature is available.
+    #[target_feature(enable = "ssse3")]
+    pub unsafe fn find_first(lo_table: &[u8; 16], hi_table: &[u8; 16], bytes: &[u8]) -> Option<usize> {
+        let lo_vec = _mm_loadu_si128(lo_table.as_ptr() as *const __m128i);
+        let hi_vec = _mm_loadu_si128(hi_table.as_ptr() as *const __m128i);
+        let mut chunks = bytes.chunks_exact(16);
+        let mut offset = 0;
+        for chunk in &mut chunks {
+            let member_mask = classify16(lo_vec, hi_vec, chunk);
+            if member_mask != 0 {
+                return Some(offset + member_mask.trailing_zeros() as usize);
+            }
+            offset += 16;
+        }
+        super::find_first_scalar(lo_table, hi_table, chunks.remainder()).map(|i| offset + i)
+    }
+
+    /// # Safety
+    /// The caller must ensure the `ssse3` target feature is available.
+    #[target_feature(enable = "ssse3")]
+    pub unsafe fn retain_matching(lo_table: &[u8; 16], hi_table: &[u8; 16], bytes: &[u8]) -> Vec<u8> {
+        let lo_vec = _mm_loadu_si128(lo_table.as_ptr() as *const __m128i);
+        let hi_vec = _mm_loadu_si128(hi_table.as_ptr() as *const __m128i);
+        let mut out = Vec::with_capacity(bytes.len());
+        let mut chunks = bytes.chunks_exact(16);
+        for chunk in &mut chunks {
+            // Classify all 16 bytes at once with the two pshufb gathers in `classify16`, then walk
+            // the set bits of the resulting mask (ascending, so order is preserved) and push just
+            // the matching bytes, looping once per match instead of once per byte.
+            let mut member_mask = classify16(lo_vec, hi_vec, chunk);
+            while member_mask != 0 {
+                let i = member_mask.trailing_zeros() as usize;
+                out.push(chunk[i]);
+                member_mask &= member_mask - 1;
+            }
+        }
+        out.extend_from_slice(&super::retain_matching_scalar(lo_table, hi_table, chunks.remainder()));
+        out
+    }
+
+    /// Classifies 16 bytes at once, returning a bitmask (bit `i` set iff byte `i` is a member).
+    ///
+    /// # Safety
+    /// The caller must ensure the `ssse3` target feature is available.
+    #[target_feature(enable = "ssse3")]
+    unsafe fn classify16(lo_table: __m128i, hi_table: __m128i, chunk: &[u8]) -> u32 {
+        let v = _mm_loadu_si128(chunk.as_ptr() as *const __m128i);
+        let lo_nibble = _mm_and_si128(v, _mm_set1_epi8(0x0F));
+        let hi_nibble = _mm_and_si128(_mm_srli_epi16(v, 4), _mm_set1_epi8(0x0F));
+        let lo_row = _mm_shuffle_epi8(lo_table, lo_nibble);
+        let hi_row = _mm_shuffle_epi8(hi_table, hi_nibble);
+        let matched = _mm_and_si128(lo_row, hi_row);
+        let is_nonmember = _mm_cmpeq_epi8(matched, _mm_setzero_si128());
+        let nonmember_mask = _mm_movemask_epi8(is_nonmember) as u32 & 0xFFFF;
+        (!nonmember_mask) & 0xFFFF
+    }
 }
 
 #[cfg(test)]
@@ -235,5 +877,114 @@ mod tests {
         assert_eq!(AsciiSet::letters(), AsciiSet::from_ranges(vec![('A', 'Z'), ('a', 'z')]));
         assert_eq!(AsciiSet::digits(), AsciiSet::from_ranges(vec![('0', '9')]));
     }
+
+    #[test]
+    fn builtin_classes() {
+        assert_eq!(AsciiSet::hex_digits(), AsciiSet::from_fn(|c| c.is_ascii_hexdigit()));
+        assert_eq!(AsciiSet::alphanumeric(), AsciiSet::from_fn(|c| c.is_ascii_alphanumeric()));
+        assert_eq!(
+            AsciiSet::whitespace(),
+            AsciiSet::from_fn(|c| " \t\n\x0B\x0C\r".contains(c)));
+        assert_eq!(AsciiSet::blank(), AsciiSet::from_fn(|c| c == ' ' || c == '\t'));
+        assert_eq!(AsciiSet::control(), AsciiSet::from_fn(|c| c.is_ascii_control()));
+        assert_eq!(AsciiSet::punctuation(), AsciiSet::from_fn(|c| c.is_ascii_punctuation()));
+        assert_eq!(AsciiSet::graphic(), AsciiSet::from_fn(|c| c.is_ascii_graphic()));
+        assert_eq!(
+            AsciiSet::printable(),
+            AsciiSet::from_fn(|c| c.is_ascii_graphic() || c == ' '));
+    }
+
+    #[test]
+    fn batch_ops() {
+        let digits = AsciiSet::digits();
+        let input: Vec<u8> = (0..200).map(|i| b'a' + (i % 26) as u8).collect();
+        let mut input = input;
+        input.extend_from_slice(b"abc123xyz456");
+
+        let expected_count = input.iter().filter(|&&b| digits.contains_byte(b)).count();
+        assert_eq!(digits.count_in(&input), expected_count);
+
+        let expected_first = input.iter().position(|&b| digits.contains_byte(b));
+        assert_eq!(digits.find_first(&input), expected_first);
+
+        let expected_retained: Vec<u8> =
+            input.iter().cloned().filter(|&b| digits.contains_byte(b)).collect();
+        assert_eq!(digits.retain_matching(&input), expected_retained);
+
+        assert_eq!(AsciiSet::letters().find_first(b"   "), None);
+        assert_eq!(AsciiSet::letters().count_in(b""), 0);
+    }
+
+    #[test]
+    fn case_insensitive() {
+        let lower_vowels = AsciiSet::from_chars("aeiou".chars());
+        assert!(lower_vowels.contains_char_ignore_case('a'));
+        assert!(lower_vowels.contains_char_ignore_case('A'));
+        assert!(!lower_vowels.contains_char_ignore_case('b'));
+        assert!(!lower_vowels.contains_char_ignore_case('B'));
+
+        let mut set = AsciiSet::new();
+        set.insert_char_ignore_case('x');
+        assert!(set.contains_char('x'));
+        assert!(set.contains_char('X'));
+
+        let mut digit_or_letter = AsciiSet::digits();
+        digit_or_letter.insert_char_ignore_case('5');
+        assert_eq!(digit_or_letter, AsciiSet::digits());
+
+        assert_eq!(
+            lower_vowels.to_case_insensitive(),
+            AsciiSet::from_chars("aeiouAEIOU".chars()));
+        assert_eq!(AsciiSet::digits().to_case_insensitive(), AsciiSet::digits());
+    }
+
+    #[test]
+    fn iteration_and_cardinality() {
+        let a = AsciiSet::from_ranges(vec![('a', 'c'), ('x', 'z')]);
+        assert_eq!(a.len(), 6);
+        assert!(!a.is_empty());
+        assert!(AsciiSet::new().is_empty());
+        assert_eq!(a.iter().collect::<Vec<_>>(), vec!['a', 'b', 'c', 'x', 'y', 'z']);
+        assert_eq!(
+            a.iter_bytes().collect::<Vec<_>>(),
+            vec![b'a', b'b', b'c', b'x', b'y', b'z']);
+    }
+
+    #[test]
+    fn display_and_from_str() {
+        let a = AsciiSet::digits().union(&AsciiSet::letters());
+        assert_eq!(a.to_string(), "0-9A-Za-z");
+        assert_eq!(a.to_string().parse::<AsciiSet>().unwrap(), a);
+
+        let with_dash_and_newline = AsciiSet::from_chars("-\n".chars());
+        let printed = with_dash_and_newline.to_string();
+        assert_eq!(printed.parse::<AsciiSet>().unwrap(), with_dash_and_newline);
+
+        assert!("a-".parse::<AsciiSet>().is_err());
+        assert!("\\xZZ".parse::<AsciiSet>().is_err());
+        assert!("\\x80".parse::<AsciiSet>().is_err());
+        assert!("z-a".parse::<AsciiSet>().is_err());
+    }
+
+    #[test]
+    fn str_patterns() {
+        let digits = AsciiSet::digits();
+        assert_eq!(digits.find("abc123"), Some(3));
+        assert_eq!(digits.find("abcxyz"), None);
+        assert_eq!(digits.rfind("a1b2c3d"), Some(5));
+        assert_eq!(digits.rfind("abcxyz"), None);
+        assert_eq!(digits.matches_count("a1b22c"), 3);
+
+        let commas = AsciiSet::from_chars(",".chars());
+        assert_eq!(commas.split("a,bc,,d").collect::<Vec<_>>(), vec!["a", "bc", "", "d"]);
+        assert_eq!(commas.split("").collect::<Vec<_>>(), vec![""]);
+        assert_eq!(commas.split("abc").collect::<Vec<_>>(), vec!["abc"]);
+
+        let ws = AsciiSet::whitespace();
+        assert_eq!(ws.trim("  hi  "), "hi");
+        assert_eq!(ws.trim_start("  hi  "), "hi  ");
+        assert_eq!(ws.trim_end("  hi  "), "  hi");
+        assert_eq!(ws.trim("   "), "");
+    }
 }
 